@@ -14,6 +14,12 @@ pub enum DiceError {
     #[error("Quantity limit exceeded: {0} (maximum is 1000)")]
     QuantityLimitExceeded(i32),
 
+    #[error("Keep/drop count {0} exceeds quantity {1}")]
+    KeepDropCountExceedsQuantity(u32, i32),
+
+    #[error("Variable not found: {0}")]
+    VariableNotFound(String),
+
     #[error("Parse error: {0}")]
     ParseError(#[from] std::num::ParseIntError),
 }
@@ -2,16 +2,316 @@ mod error;
 mod model;
 mod parser;
 
+use std::collections::HashMap;
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
 pub use error::DiceError;
-pub use model::RollResult;
-pub use parser::{DiceRequest, dice_result};
+pub use model::{PercentileResult, PoolResult, RollResult};
+pub use parser::{
+    DiceGroup, DiceRequest, Element, KeepKind, Operand, Operator, PercentileModifier,
+    PercentileRequest, PoolRequest, Term, dice_result, percentile_result, pool_result,
+};
+
+const MAX_QUANTITY: i32 = 1000;
+const MAX_POOL_REROLLS: usize = 100;
 
+/// Rolls `expression` using a freshly generated random seed.
+///
+/// See [`roll_with_seed`] for the full validation and evaluation rules.
 pub fn roll(expression: &str) -> Result<RollResult, DiceError> {
-    Err(DiceError::InvalidFormat("Not implemented".to_string()))
+    let mut seed = [0u8; 32];
+    rand::rng().fill_bytes(&mut seed);
+    roll_with_seed(expression, seed)
 }
 
+/// Rolls `expression` against a `ChaCha20Rng` seeded with `seed`.
+///
+/// Reusing the same seed reproduces byte-for-byte identical results, which
+/// makes replays and tests deterministic across runs and platforms.
+///
+/// `expression` may chain several terms together, e.g.
+/// `2d6 + 1d8 - 2 + 3d10`: every dice group's rolls are concatenated into
+/// `RollResult.dice_rolls`, and each term's signed value (the retained
+/// dice total, or the constant itself) is folded into `RollResult.total`.
+/// An expression referencing a named variable fails with
+/// [`DiceError::VariableNotFound`]; use [`roll_with_vars`] to supply values.
 pub fn roll_with_seed(expression: &str, seed: [u8; 32]) -> Result<RollResult, DiceError> {
-    todo!()
+    roll_with_vars(expression, &HashMap::new(), seed)
+}
+
+/// Rolls `expression` against a `ChaCha20Rng` seeded with `seed`, resolving
+/// any named variables (e.g. `strength` in `2d6 + strength`, or `gnosis` in
+/// `gnosis d10`) from `vars`. Fails with [`DiceError::VariableNotFound`] if
+/// `expression` references a name that isn't in `vars`.
+pub fn roll_with_vars(
+    expression: &str,
+    vars: &HashMap<String, i32>,
+    seed: [u8; 32],
+) -> Result<RollResult, DiceError> {
+    let (remaining, request) =
+        dice_result(expression).map_err(|e| DiceError::InvalidFormat(e.to_string()))?;
+
+    if !remaining.is_empty() {
+        return Err(DiceError::InvalidFormat(remaining.to_string()));
+    }
+
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    let mut dice_rolls = Vec::new();
+    let mut total = 0;
+    let mut modifier = 0;
+
+    for term in &request.terms {
+        let sign = match term.operator {
+            Operator::Plus => 1,
+            Operator::Minus => -1,
+        };
+
+        match &term.element {
+            Element::Dice(group) => {
+                let quantity = resolve_operand(&group.quantity, vars)?;
+                let rolls =
+                    roll_dice_group(quantity, group.sides, group.keep, group.drop, &mut rng)?;
+                total += sign * retained_sum(&rolls, group.keep, group.drop);
+                dice_rolls.extend(rolls);
+            }
+            Element::Constant(value) => {
+                total += sign * value;
+                modifier += sign * value;
+            }
+            Element::Variable(name) => {
+                let value = resolve_variable(name, vars)?;
+                total += sign * value;
+                modifier += sign * value;
+            }
+        }
+    }
+
+    Ok(RollResult {
+        total,
+        dice_rolls,
+        modifier,
+    })
+}
+
+fn resolve_operand(operand: &Operand, vars: &HashMap<String, i32>) -> Result<i32, DiceError> {
+    match operand {
+        Operand::Literal(value) => Ok(*value),
+        Operand::Variable(name) => resolve_variable(name, vars),
+    }
+}
+
+fn resolve_variable(name: &str, vars: &HashMap<String, i32>) -> Result<i32, DiceError> {
+    vars.get(name)
+        .copied()
+        .ok_or_else(|| DiceError::VariableNotFound(name.to_string()))
+}
+
+/// Validates and rolls a single dice group, returning every individual die.
+fn roll_dice_group(
+    quantity: i32,
+    sides: i32,
+    keep: Option<(KeepKind, u32)>,
+    drop: Option<u32>,
+    rng: &mut ChaCha20Rng,
+) -> Result<Vec<i32>, DiceError> {
+    if quantity < 1 {
+        return Err(DiceError::InvalidQuantity(quantity));
+    }
+    if quantity > MAX_QUANTITY {
+        return Err(DiceError::QuantityLimitExceeded(quantity));
+    }
+    if sides < 1 {
+        return Err(DiceError::InvalidDieSize(sides));
+    }
+    if let Some((_, count)) = keep {
+        if count as i32 > quantity {
+            return Err(DiceError::KeepDropCountExceedsQuantity(count, quantity));
+        }
+    }
+    if let Some(count) = drop {
+        if count as i32 > quantity {
+            return Err(DiceError::KeepDropCountExceedsQuantity(count, quantity));
+        }
+    }
+
+    Ok((0..quantity).map(|_| rng.random_range(1..=sides)).collect())
+}
+
+/// Sums only the dice retained by a `keep`/`drop` clause, falling back to
+/// every die when neither clause is present. `dice_rolls` is reported to the
+/// caller unsorted and un-filtered so every die — including dropped ones
+/// — stays visible.
+fn retained_sum(dice_rolls: &[i32], keep: Option<(KeepKind, u32)>, drop: Option<u32>) -> i32 {
+    let mut sorted = dice_rolls.to_vec();
+    sorted.sort_unstable();
+
+    match (keep, drop) {
+        (Some((KeepKind::Highest, count)), _) => {
+            sorted.iter().rev().take(count as usize).sum()
+        }
+        (Some((KeepKind::Lowest, count)), _) => sorted.iter().take(count as usize).sum(),
+        (None, Some(count)) => sorted.iter().skip(count as usize).sum(),
+        (None, None) => dice_rolls.iter().sum(),
+    }
+}
+
+/// Rolls a Chronicles-of-Darkness-style success-counting pool using a
+/// freshly generated random seed.
+///
+/// See [`roll_pool_with_seed`] for the full evaluation rules.
+pub fn roll_pool(expression: &str) -> Result<PoolResult, DiceError> {
+    let mut seed = [0u8; 32];
+    rand::rng().fill_bytes(&mut seed);
+    roll_pool_with_seed(expression, seed)
+}
+
+/// Rolls a success-counting dice pool (e.g. `5d10n`) against a `ChaCha20Rng`
+/// seeded with `seed`.
+///
+/// Each die meeting or exceeding `target` (8 by default) counts as a
+/// success. Dice landing at or above the pool's again-threshold (10 for
+/// plain pools, 9 for `n`, 8 for `e`) trigger an additional reroll, and a
+/// rerolled die that also lands in that range triggers another, recursively,
+/// capped at [`MAX_POOL_REROLLS`] total rerolls to bound worst-case pools.
+/// With the `rote` quality, a die that fails on its very first roll gets one
+/// reroll, which can itself chain through the again rule.
+pub fn roll_pool_with_seed(expression: &str, seed: [u8; 32]) -> Result<PoolResult, DiceError> {
+    let (remaining, request) =
+        pool_result(expression).map_err(|e| DiceError::InvalidFormat(e.to_string()))?;
+
+    if !remaining.is_empty() {
+        return Err(DiceError::InvalidFormat(remaining.to_string()));
+    }
+    if request.count < 1 {
+        return Err(DiceError::InvalidQuantity(request.count));
+    }
+    if request.count > MAX_QUANTITY {
+        return Err(DiceError::QuantityLimitExceeded(request.count));
+    }
+
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    let mut rerolls_remaining = MAX_POOL_REROLLS;
+    let mut dice_rolls = Vec::new();
+    let mut successes = 0;
+
+    for _ in 0..request.count {
+        let (rolls, rolled_successes) =
+            roll_again_chain(&mut rng, &request, &mut rerolls_remaining);
+        let first_face = rolls[0];
+        dice_rolls.extend(rolls);
+        successes += rolled_successes;
+
+        if request.rote && first_face < request.target && rerolls_remaining > 0 {
+            rerolls_remaining -= 1;
+            let (rote_rolls, rote_successes) =
+                roll_again_chain(&mut rng, &request, &mut rerolls_remaining);
+            dice_rolls.extend(rote_rolls);
+            successes += rote_successes;
+        }
+    }
+
+    Ok(PoolResult {
+        successes,
+        dice_rolls,
+    })
+}
+
+/// Rolls one d10 and follows its "again" chain, returning every face rolled
+/// (the original plus any rerolls) and the number of successes among them.
+fn roll_again_chain(
+    rng: &mut ChaCha20Rng,
+    request: &PoolRequest,
+    rerolls_remaining: &mut usize,
+) -> (Vec<i32>, i32) {
+    let mut rolls = Vec::new();
+    let mut successes = 0;
+
+    let mut face = rng.random_range(1..=10);
+    rolls.push(face);
+    if face >= request.target {
+        successes += 1;
+    }
+
+    while face as u32 >= request.again_threshold && *rerolls_remaining > 0 {
+        *rerolls_remaining -= 1;
+        face = rng.random_range(1..=10);
+        rolls.push(face);
+        if face >= request.target {
+            successes += 1;
+        }
+    }
+
+    (rolls, successes)
+}
+
+/// Rolls a Call-of-Cthulhu percentile using a freshly generated random seed.
+///
+/// See [`roll_percentile_with_seed`] for the full bonus/penalty rules.
+pub fn roll_percentile(expression: &str) -> Result<PercentileResult, DiceError> {
+    let mut seed = [0u8; 32];
+    rand::rng().fill_bytes(&mut seed);
+    roll_percentile_with_seed(expression, seed)
+}
+
+/// Rolls a d100 (`d100`, `d100b`/`d100bb`, `d100p`/`d100pp`) against a
+/// `ChaCha20Rng` seeded with `seed`.
+///
+/// A bonus die rolls one extra tens die and keeps the lowest combined
+/// total; a penalty die rolls one extra and keeps the highest; two
+/// bonus/penalty dice roll two extras. `rolls_considered` lists every
+/// candidate total so the player can verify which one was selected. A tens
+/// result of 0 combined with a non-zero units die is always treated as that
+/// units value alone (1-9), not 100 - only an all-zero roll is 100.
+pub fn roll_percentile_with_seed(
+    expression: &str,
+    seed: [u8; 32],
+) -> Result<PercentileResult, DiceError> {
+    let (remaining, request) =
+        percentile_result(expression).map_err(|e| DiceError::InvalidFormat(e.to_string()))?;
+
+    if !remaining.is_empty() {
+        return Err(DiceError::InvalidFormat(remaining.to_string()));
+    }
+
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    let units = rng.random_range(0..=9);
+    let base_tens = rng.random_range(0..=9) * 10;
+
+    let extra_tens = match request.modifier {
+        Some(PercentileModifier::Bonus(n)) | Some(PercentileModifier::Penalty(n)) => n,
+        None => 0,
+    };
+
+    let mut tens_candidates = vec![base_tens];
+    for _ in 0..extra_tens {
+        tens_candidates.push(rng.random_range(0..=9) * 10);
+    }
+
+    let rolls_considered: Vec<i32> = tens_candidates
+        .into_iter()
+        .map(|tens| percentile_total(tens, units))
+        .collect();
+
+    let result = match request.modifier {
+        Some(PercentileModifier::Bonus(_)) => *rolls_considered.iter().min().unwrap(),
+        Some(PercentileModifier::Penalty(_)) => *rolls_considered.iter().max().unwrap(),
+        None => rolls_considered[0],
+    };
+
+    Ok(PercentileResult {
+        rolls_considered,
+        result,
+    })
+}
+
+fn percentile_total(tens: i32, units: i32) -> i32 {
+    if tens == 0 && units == 0 {
+        100
+    } else {
+        tens + units
+    }
 }
 
 #[cfg(test)]
@@ -49,47 +349,329 @@ mod tests {
         assert_eq!(result.to_string(), "[18] = 18");
     }
 
+    /// Extracts the single dice group out of a one-term request, panicking
+    /// if the request isn't shaped that way - a convenience for tests that
+    /// predate multi-term expressions.
+    fn only_group(request: &DiceRequest) -> &DiceGroup {
+        assert_eq!(request.terms.len(), 1);
+        match &request.terms[0].element {
+            Element::Dice(group) => group,
+            Element::Constant(_) => panic!("expected a dice group, got a constant"),
+            Element::Variable(_) => panic!("expected a dice group, got a variable"),
+        }
+    }
+
     #[test]
     fn test_parse_dx() {
         let (remaining, request) = dice_result("d6").unwrap();
         assert_eq!(remaining, "");
-        assert_eq!(request.quantity, 1);
-        assert_eq!(request.sides, 6);
-        assert_eq!(request.modifier, 0);
+        let group = only_group(&request);
+        assert_eq!(group.quantity, Operand::Literal(1));
+        assert_eq!(group.sides, 6);
     }
 
     #[test]
     fn test_parse_simple_adx() {
         let (remaining, request) = dice_result("2d6").unwrap();
         assert_eq!(remaining, "");
-        assert_eq!(request.quantity, 2);
-        assert_eq!(request.sides, 6);
-        assert_eq!(request.modifier, 0);
+        let group = only_group(&request);
+        assert_eq!(group.quantity, Operand::Literal(2));
+        assert_eq!(group.sides, 6);
     }
+
     #[test]
     fn test_parse_with_positive_modifier() {
         let (remaining, request) = dice_result("2d6+5").unwrap();
         assert_eq!(remaining, "");
-        assert_eq!(request.quantity, 2);
-        assert_eq!(request.sides, 6);
-        assert_eq!(request.modifier, 5);
+        assert_eq!(request.terms.len(), 2);
+        assert_eq!(request.terms[1].operator, Operator::Plus);
+        assert_eq!(request.terms[1].element, Element::Constant(5));
     }
 
     #[test]
     fn test_parse_with_negative_modifier() {
         let (remaining, request) = dice_result("2d6-5").unwrap();
         assert_eq!(remaining, "");
-        assert_eq!(request.quantity, 2);
-        assert_eq!(request.sides, 6);
-        assert_eq!(request.modifier, -5);
+        assert_eq!(request.terms.len(), 2);
+        assert_eq!(request.terms[1].operator, Operator::Minus);
+        assert_eq!(request.terms[1].element, Element::Constant(5));
     }
 
     #[test]
     fn test_parse_with_whitespace() {
         let (remaining, request) = dice_result(" 2d6 +5").unwrap();
         assert_eq!(remaining, "");
-        assert_eq!(request.quantity, 2);
-        assert_eq!(request.sides, 6);
-        assert_eq!(request.modifier, 5);
+        assert_eq!(request.terms.len(), 2);
+        assert_eq!(
+            request.terms[0].element,
+            Element::Dice(DiceGroup {
+                quantity: Operand::Literal(2),
+                sides: 6,
+                keep: None,
+                drop: None,
+            })
+        );
+        assert_eq!(request.terms[1].element, Element::Constant(5));
+    }
+
+    #[test]
+    fn test_parse_multi_term_expression() {
+        let (remaining, request) = dice_result("2d6 + 1d8 - 2 + 3d10").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.terms.len(), 4);
+        assert_eq!(request.terms[0].operator, Operator::Plus);
+        assert_eq!(
+            request.terms[0].element,
+            Element::Dice(DiceGroup {
+                quantity: Operand::Literal(2),
+                sides: 6,
+                keep: None,
+                drop: None,
+            })
+        );
+        assert_eq!(request.terms[2].operator, Operator::Minus);
+        assert_eq!(request.terms[2].element, Element::Constant(2));
+        assert_eq!(request.terms[3].operator, Operator::Plus);
+        assert_eq!(
+            request.terms[3].element,
+            Element::Dice(DiceGroup {
+                quantity: Operand::Literal(3),
+                sides: 10,
+                keep: None,
+                drop: None,
+            })
+        );
+    }
+
+    #[test]
+    fn roll_evaluates_multi_term_expression() {
+        let result = roll_with_seed("2d6 + 1d8 - 2 + 3d10", [4u8; 32]).unwrap();
+        assert_eq!(result.dice_rolls.len(), 6);
+        assert!(result.dice_rolls[..2].iter().all(|&r| (1..=6).contains(&r)));
+        assert!((1..=8).contains(&result.dice_rolls[2]));
+        assert!(result.dice_rolls[3..].iter().all(|&r| (1..=10).contains(&r)));
+        let dice_sum: i32 = result.dice_rolls.iter().sum();
+        assert_eq!(result.total, dice_sum - 2);
+    }
+
+    #[test]
+    fn roll_with_seed_is_deterministic() {
+        let seed = [7u8; 32];
+        let first = roll_with_seed("4d6+2", seed).unwrap();
+        let second = roll_with_seed("4d6+2", seed).unwrap();
+        assert_eq!(first.dice_rolls, second.dice_rolls);
+        assert_eq!(first.total, second.total);
+    }
+
+    #[test]
+    fn roll_with_seed_sums_dice_and_modifier() {
+        let result = roll_with_seed("3d6-1", [1u8; 32]).unwrap();
+        assert_eq!(result.dice_rolls.len(), 3);
+        assert!(result.dice_rolls.iter().all(|&r| (1..=6).contains(&r)));
+        let sum: i32 = result.dice_rolls.iter().sum();
+        assert_eq!(result.total, sum - 1);
+    }
+
+    #[test]
+    fn roll_rejects_quantity_over_limit() {
+        let err = roll_with_seed("1001d6", [0u8; 32]).unwrap_err();
+        assert!(matches!(err, DiceError::QuantityLimitExceeded(1001)));
+    }
+
+    #[test]
+    fn roll_rejects_trailing_garbage() {
+        let err = roll_with_seed("2d6 @@@", [0u8; 32]).unwrap_err();
+        assert!(matches!(err, DiceError::InvalidFormat(_)));
+    }
+
+    #[test]
+    fn test_parse_keep_highest() {
+        let (remaining, request) = dice_result("4d6k3").unwrap();
+        assert_eq!(remaining, "");
+        let group = only_group(&request);
+        assert_eq!(group.keep, Some((KeepKind::Highest, 3)));
+        assert_eq!(group.drop, None);
+    }
+
+    #[test]
+    fn test_parse_keep_lowest() {
+        let (remaining, request) = dice_result("4d6kl1").unwrap();
+        assert_eq!(remaining, "");
+        let group = only_group(&request);
+        assert_eq!(group.keep, Some((KeepKind::Lowest, 1)));
+        assert_eq!(group.drop, None);
+    }
+
+    #[test]
+    fn test_parse_drop_lowest() {
+        let (remaining, request) = dice_result("4d6d1").unwrap();
+        assert_eq!(remaining, "");
+        let group = only_group(&request);
+        assert_eq!(group.keep, None);
+        assert_eq!(group.drop, Some(1));
+    }
+
+    #[test]
+    fn roll_keep_highest_reports_all_dice_but_sums_only_kept() {
+        let result = roll_with_seed("4d6k3", [3u8; 32]).unwrap();
+        assert_eq!(result.dice_rolls.len(), 4);
+        let mut sorted = result.dice_rolls.clone();
+        sorted.sort_unstable();
+        let expected: i32 = sorted.iter().rev().take(3).sum();
+        assert_eq!(result.total, expected);
+    }
+
+    #[test]
+    fn roll_drop_lowest_reports_all_dice_but_sums_only_kept() {
+        let result = roll_with_seed("4d6d1", [3u8; 32]).unwrap();
+        assert_eq!(result.dice_rolls.len(), 4);
+        let mut sorted = result.dice_rolls.clone();
+        sorted.sort_unstable();
+        let expected: i32 = sorted.iter().skip(1).sum();
+        assert_eq!(result.total, expected);
+    }
+
+    #[test]
+    fn roll_rejects_keep_count_over_quantity() {
+        let err = roll_with_seed("4d6k5", [0u8; 32]).unwrap_err();
+        assert!(matches!(err, DiceError::KeepDropCountExceedsQuantity(5, 4)));
+    }
+
+    #[test]
+    fn test_parse_pool_defaults_to_ten_again() {
+        let (remaining, request) = pool_result("5d10").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.count, 5);
+        assert_eq!(request.again_threshold, 10);
+        assert_eq!(request.target, 8);
+        assert!(!request.rote);
+    }
+
+    #[test]
+    fn test_parse_pool_nine_again_and_rote() {
+        let (remaining, request) = pool_result("5d10nr").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.again_threshold, 9);
+        assert!(request.rote);
+    }
+
+    #[test]
+    fn roll_pool_counts_successes_at_or_above_target() {
+        let result = roll_pool_with_seed("10d10", [5u8; 32]).unwrap();
+        let expected: i32 = result.dice_rolls.iter().filter(|&&r| r >= 8).count() as i32;
+        assert_eq!(result.successes, expected);
+    }
+
+    #[test]
+    fn roll_pool_again_chain_can_roll_more_dice_than_requested() {
+        let result = roll_pool_with_seed("20d10n", [9u8; 32]).unwrap();
+        let expected: i32 = result.dice_rolls.iter().filter(|&&r| r >= 8).count() as i32;
+        assert_eq!(result.successes, expected);
+        assert!(result.dice_rolls.len() >= 20);
+    }
+
+    #[test]
+    fn roll_pool_rejects_quantity_over_limit() {
+        let err = roll_pool_with_seed("1001d10", [0u8; 32]).unwrap_err();
+        assert!(matches!(err, DiceError::QuantityLimitExceeded(1001)));
+    }
+
+    #[test]
+    fn test_parse_percentile_plain() {
+        let (remaining, request) = percentile_result("d100").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.modifier, None);
+    }
+
+    #[test]
+    fn test_parse_percentile_bonus_and_penalty() {
+        let (remaining, request) = percentile_result("d100bb").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.modifier, Some(PercentileModifier::Bonus(2)));
+
+        let (remaining, request) = percentile_result("d100p").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.modifier, Some(PercentileModifier::Penalty(1)));
+    }
+
+    #[test]
+    fn roll_percentile_plain_has_single_candidate() {
+        let result = roll_percentile_with_seed("d100", [2u8; 32]).unwrap();
+        assert_eq!(result.rolls_considered.len(), 1);
+        assert_eq!(result.result, result.rolls_considered[0]);
+        assert!((1..=100).contains(&result.result));
+    }
+
+    #[test]
+    fn roll_percentile_bonus_keeps_lowest_candidate() {
+        let result = roll_percentile_with_seed("d100b", [2u8; 32]).unwrap();
+        assert_eq!(result.rolls_considered.len(), 2);
+        assert_eq!(result.result, *result.rolls_considered.iter().min().unwrap());
+    }
+
+    #[test]
+    fn roll_percentile_penalty_keeps_highest_candidate() {
+        let result = roll_percentile_with_seed("d100pp", [2u8; 32]).unwrap();
+        assert_eq!(result.rolls_considered.len(), 3);
+        assert_eq!(result.result, *result.rolls_considered.iter().max().unwrap());
+    }
+
+    #[test]
+    fn percentile_total_zero_tens_and_nonzero_units_is_not_one_hundred() {
+        assert_eq!(percentile_total(0, 4), 4);
+        assert_eq!(percentile_total(0, 0), 100);
+        assert_eq!(percentile_total(30, 0), 30);
+    }
+
+    #[test]
+    fn test_parse_variable_element() {
+        let (remaining, request) = dice_result("2d6 + strength").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(request.terms.len(), 2);
+        assert_eq!(
+            request.terms[1].element,
+            Element::Variable("strength".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_quantity() {
+        let (remaining, request) = dice_result("gnosis d10").unwrap();
+        assert_eq!(remaining, "");
+        let group = only_group(&request);
+        assert_eq!(group.quantity, Operand::Variable("gnosis".to_string()));
+        assert_eq!(group.sides, 10);
+    }
+
+    #[test]
+    fn roll_with_vars_resolves_named_constant() {
+        let mut vars = HashMap::new();
+        vars.insert("strength".to_string(), 4);
+        let result = roll_with_vars("2d6 + strength", &vars, [1u8; 32]).unwrap();
+        assert_eq!(result.dice_rolls.len(), 2);
+        let dice_sum: i32 = result.dice_rolls.iter().sum();
+        assert_eq!(result.total, dice_sum + 4);
+        assert_eq!(result.modifier, 4);
+    }
+
+    #[test]
+    fn roll_with_vars_resolves_variable_quantity() {
+        let mut vars = HashMap::new();
+        vars.insert("gnosis".to_string(), 3);
+        let result = roll_with_vars("gnosis d10", &vars, [1u8; 32]).unwrap();
+        assert_eq!(result.dice_rolls.len(), 3);
+        assert!(result.dice_rolls.iter().all(|&r| (1..=10).contains(&r)));
+    }
+
+    #[test]
+    fn roll_with_vars_rejects_missing_variable() {
+        let err = roll_with_vars("2d6 + strength", &HashMap::new(), [1u8; 32]).unwrap_err();
+        assert!(matches!(err, DiceError::VariableNotFound(name) if name == "strength"));
+    }
+
+    #[test]
+    fn roll_with_seed_rejects_expression_with_unresolved_variable() {
+        let err = roll_with_seed("2d6 + strength", [1u8; 32]).unwrap_err();
+        assert!(matches!(err, DiceError::VariableNotFound(name) if name == "strength"));
     }
 }
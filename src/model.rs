@@ -1,5 +1,6 @@
 use std::fmt;
 
+#[derive(Debug)]
 pub struct RollResult {
     pub total: i32,
     pub dice_rolls: Vec<i32>,
@@ -25,3 +26,29 @@ impl fmt::Display for RollResult {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct PoolResult {
+    pub successes: i32,
+    pub dice_rolls: Vec<i32>,
+}
+
+impl fmt::Display for PoolResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} = {} successes", self.dice_rolls, self.successes)
+    }
+}
+
+/// Every candidate total considered for a Call-of-Cthulhu bonus/penalty
+/// roll, plus the one actually selected.
+#[derive(Debug)]
+pub struct PercentileResult {
+    pub rolls_considered: Vec<i32>,
+    pub result: i32,
+}
+
+impl fmt::Display for PercentileResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} -> {}", self.rolls_considered, self.result)
+    }
+}
@@ -1,14 +1,63 @@
 use nom::bytes::complete::tag;
-use nom::character::complete::{digit0, digit1, one_of};
-use nom::combinator::recognize;
-use nom::sequence::pair;
-use nom::{IResult, Parser, branch::alt, combinator::map_res};
+use nom::character::complete::{alpha1, alphanumeric1, digit0, digit1, multispace0, one_of};
+use nom::combinator::{map, map_res, opt, recognize};
+use nom::multi::{many0, many1};
+use nom::sequence::{pair, preceded};
+use nom::{IResult, Parser, branch::alt};
 use std::num::ParseIntError;
 
-pub struct DiceRequest {
-    pub quantity: i32,
+/// Which end of the sorted rolls a `keep` clause retains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepKind {
+    Highest,
+    Lowest,
+}
+
+/// A dice group's quantity: either a literal count or a named variable
+/// (e.g. `gnosis` in `gnosis d10`) resolved at roll time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Literal(i32),
+    Variable(String),
+}
+
+/// A single `XdY` group within an expression, e.g. the `2d6` in `2d6 + 1d8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceGroup {
+    pub quantity: Operand,
     pub sides: i32,
-    pub modifier: i32,
+    pub keep: Option<(KeepKind, u32)>,
+    pub drop: Option<u32>,
+}
+
+/// A single piece of a dice expression: a dice group, a flat constant, or a
+/// named variable (e.g. `strength` in `2d6 + strength`) resolved at roll
+/// time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Element {
+    Dice(DiceGroup),
+    Constant(i32),
+    Variable(String),
+}
+
+/// Whether a [`Term`] is added to or subtracted from the running total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Plus,
+    Minus,
+}
+
+/// One signed element of a dice expression, e.g. `+ 2d6` or `- 3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub operator: Operator,
+    pub element: Element,
+}
+
+/// A full dice expression such as `2d6 + 1d8 - 2 + 3d10`, as a sequence of
+/// signed terms.
+pub struct DiceRequest {
+    pub terms: Vec<Term>,
 }
 
 fn str_to_i32(str: &str) -> Result<i32, ParseIntError> {
@@ -19,6 +68,10 @@ fn str_to_i32_or_one(s: &str) -> Result<i32, ParseIntError> {
     if s.is_empty() { Ok(1) } else { str_to_i32(s) }
 }
 
+fn str_to_u32(str: &str) -> Result<u32, ParseIntError> {
+    str.parse::<u32>()
+}
+
 fn parse_quantity(input: &str) -> IResult<&str, i32> {
     map_res(digit0, str_to_i32_or_one).parse(input)
 }
@@ -31,20 +84,197 @@ fn parse_d(input: &str) -> IResult<&str, &str> {
     alt((tag("d"), tag("D"))).parse(input)
 }
 
-fn parse_modifier(input: &str) -> IResult<&str, i32> {
-    map_res(recognize(pair(one_of("+-"), digit1)), str_to_i32).parse(input)
+/// Parses an identifier token (a variable name): letters, digits, and
+/// underscores, starting with a letter.
+fn parse_identifier(input: &str) -> IResult<&str, String> {
+    map(
+        recognize(pair(alpha1, many0(alt((alphanumeric1, tag("_")))))),
+        String::from,
+    )
+    .parse(input)
+}
+
+fn parse_operator(input: &str) -> IResult<&str, Operator> {
+    map(one_of("+-"), |c| {
+        if c == '+' { Operator::Plus } else { Operator::Minus }
+    })
+    .parse(input)
+}
+
+/// The `(keep, drop)` clause trailing a dice group, as parsed by
+/// [`parse_keep_drop`].
+type KeepDropClause = (Option<(KeepKind, u32)>, Option<u32>);
+
+/// Parses a trailing keep/drop clause such as `k3` (keep highest 3), `kl1`
+/// (keep lowest 1), or `d1` (drop lowest 1).
+fn parse_keep_drop(input: &str) -> IResult<&str, KeepDropClause> {
+    let keep_lowest = map(preceded(tag("kl"), map_res(digit1, str_to_u32)), |n| {
+        (Some((KeepKind::Lowest, n)), None)
+    });
+    let keep_highest = map(preceded(tag("k"), map_res(digit1, str_to_u32)), |n| {
+        (Some((KeepKind::Highest, n)), None)
+    });
+    let drop_lowest = map(preceded(tag("d"), map_res(digit1, str_to_u32)), |n| {
+        (None, Some(n))
+    });
+
+    map(
+        opt(alt((keep_lowest, keep_highest, drop_lowest))),
+        |clause| clause.unwrap_or((None, None)),
+    )
+    .parse(input)
+}
+
+/// Parses a dice group whose quantity is a literal number (defaulting to 1
+/// when omitted, as in `d6`).
+fn parse_literal_quantity_group(input: &str) -> IResult<&str, Element> {
+    map(
+        (parse_quantity, parse_d, parse_sides, parse_keep_drop),
+        |(quantity, _d, sides, (keep, drop))| {
+            Element::Dice(DiceGroup {
+                quantity: Operand::Literal(quantity),
+                sides,
+                keep,
+                drop,
+            })
+        },
+    )
+    .parse(input)
+}
+
+/// Parses a dice group whose quantity is a variable name, e.g. the `gnosis`
+/// in `gnosis d10`. A space before the `d` separator is allowed so
+/// variable-quantity rolls read naturally.
+fn parse_variable_quantity_group(input: &str) -> IResult<&str, Element> {
+    map(
+        (
+            parse_identifier,
+            multispace0,
+            parse_d,
+            parse_sides,
+            parse_keep_drop,
+        ),
+        |(name, _, _d, sides, (keep, drop))| {
+            Element::Dice(DiceGroup {
+                quantity: Operand::Variable(name),
+                sides,
+                keep,
+                drop,
+            })
+        },
+    )
+    .parse(input)
+}
+
+fn parse_dice_group(input: &str) -> IResult<&str, Element> {
+    alt((parse_literal_quantity_group, parse_variable_quantity_group)).parse(input)
+}
+
+fn parse_constant(input: &str) -> IResult<&str, Element> {
+    map(map_res(digit1, str_to_i32), Element::Constant).parse(input)
+}
+
+fn parse_variable_element(input: &str) -> IResult<&str, Element> {
+    map(parse_identifier, Element::Variable).parse(input)
+}
+
+fn parse_element(input: &str) -> IResult<&str, Element> {
+    alt((parse_dice_group, parse_constant, parse_variable_element)).parse(input)
+}
+
+fn parse_term(input: &str) -> IResult<&str, Term> {
+    let (input, _) = multispace0(input)?;
+    let (input, operator) = opt(parse_operator).parse(input)?;
+    let (input, _) = multispace0(input)?;
+    let (input, element) = parse_element(input)?;
+
+    Ok((
+        input,
+        Term {
+            operator: operator.unwrap_or(Operator::Plus),
+            element,
+        },
+    ))
 }
 
+/// Parses an additive dice expression of one or more terms, e.g.
+/// `2d6 + 1d8 - 2 + 3d10`.
 pub fn dice_result(expression: &str) -> IResult<&str, DiceRequest> {
-    let (remaining, (quantity, _d, sides, modifier)) =
-        (parse_quantity, parse_d, parse_sides, parse_modifier).parse(expression)?;
+    let (remaining, terms) = many1(parse_term).parse(expression)?;
+    let (remaining, _) = multispace0(remaining)?;
+
+    Ok((remaining, DiceRequest { terms }))
+}
+
+/// A Chronicles-of-Darkness-style success-counting dice pool, e.g. `5d10n`
+/// (nine-again) or `5d10r` (rote).
+pub struct PoolRequest {
+    pub count: i32,
+    pub again_threshold: u32,
+    pub target: i32,
+    pub rote: bool,
+}
+
+const DEFAULT_POOL_TARGET: i32 = 8;
+const DEFAULT_AGAIN_THRESHOLD: u32 = 10;
+
+/// Parses a pool expression: a count of d10s followed by optional `n`
+/// (nine-again), `e` (eight-again), and `r` (rote) flags in any combination.
+pub fn pool_result(expression: &str) -> IResult<&str, PoolRequest> {
+    let (remaining, (count, _d, _sides, flags)) = (
+        map_res(digit1, str_to_i32),
+        parse_d,
+        tag("10"),
+        many0(one_of("nerNER")),
+    )
+        .parse(expression)?;
+
+    let flags: Vec<char> = flags.into_iter().map(|c| c.to_ascii_lowercase()).collect();
+    let again_threshold = if flags.contains(&'n') {
+        9
+    } else if flags.contains(&'e') {
+        8
+    } else {
+        DEFAULT_AGAIN_THRESHOLD
+    };
+    let rote = flags.contains(&'r');
 
     Ok((
         remaining,
-        DiceRequest {
-            quantity,
-            sides,
-            modifier,
+        PoolRequest {
+            count,
+            again_threshold,
+            target: DEFAULT_POOL_TARGET,
+            rote,
         },
     ))
 }
+
+/// A Call-of-Cthulhu d100 bonus/penalty modifier: roll 1 or 2 extra tens
+/// dice and keep the lowest (bonus) or highest (penalty) combined result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentileModifier {
+    Bonus(u8),
+    Penalty(u8),
+}
+
+pub struct PercentileRequest {
+    pub modifier: Option<PercentileModifier>,
+}
+
+/// Parses a percentile expression: `d100` followed by an optional `b`/`bb`
+/// (one/two bonus dice) or `p`/`pp` (one/two penalty dice) suffix.
+pub fn percentile_result(expression: &str) -> IResult<&str, PercentileRequest> {
+    let (remaining, (_d100, modifier)) = (
+        alt((tag("d100"), tag("D100"))),
+        opt(alt((
+            map(tag("bb"), |_| PercentileModifier::Bonus(2)),
+            map(tag("b"), |_| PercentileModifier::Bonus(1)),
+            map(tag("pp"), |_| PercentileModifier::Penalty(2)),
+            map(tag("p"), |_| PercentileModifier::Penalty(1)),
+        ))),
+    )
+        .parse(expression)?;
+
+    Ok((remaining, PercentileRequest { modifier }))
+}